@@ -0,0 +1,112 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use env_logger::Env;
+use log::kv::{self, Key, Value, VisitSource, VisitValue};
+use serde_json::{Map, Value as JsonValue};
+use std::io::Write;
+
+/// The format used to emit log records.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human readable messages (the `env_logger` default format).
+    #[default]
+    Text,
+    /// One JSON object per line, with the record's key-values as top-level fields.
+    Json,
+}
+
+/// Initializes the global logger with the given format.
+///
+/// The log level defaults to `info` and can be overridden through the `RUST_LOG`
+/// environment variable, regardless of the selected format.
+pub fn init_logger(format: LogFormat) {
+    let mut builder = env_logger::Builder::from_env(Env::default().default_filter_or("info"));
+
+    if format == LogFormat::Json {
+        builder.format(|buf, record| {
+            let mut fields = Map::new();
+            fields.insert("timestamp".into(), buf.timestamp().to_string().into());
+            fields.insert("level".into(), record.level().as_str().into());
+            fields.insert("target".into(), record.target().into());
+            fields.insert("message".into(), record.args().to_string().into());
+
+            fields.extend(key_values_to_json(record.key_values()));
+
+            writeln!(buf, "{}", JsonValue::Object(fields))
+        });
+    }
+
+    builder.init();
+}
+
+/// Collects the key-values of a log record into a JSON object.
+pub fn key_values_to_json(key_values: &dyn kv::Source) -> Map<String, JsonValue> {
+    let mut fields = Map::new();
+    let _ = key_values.visit(&mut JsonFields(&mut fields));
+    fields
+}
+
+struct JsonFields<'a>(&'a mut Map<String, JsonValue>);
+
+impl<'kvs> VisitSource<'kvs> for JsonFields<'_> {
+    fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+        let mut json_value = JsonValue::Null;
+        value.visit(ToJsonValue(&mut json_value))?;
+        self.0.insert(key.to_string(), json_value);
+        Ok(())
+    }
+}
+
+/// Converts a key-value's value into JSON, keeping the primitive types (numbers, booleans and
+/// nulls) and formatting any other value as a string.
+struct ToJsonValue<'a>(&'a mut JsonValue);
+
+impl<'v> VisitValue<'v> for ToJsonValue<'_> {
+    fn visit_any(&mut self, value: Value) -> Result<(), kv::Error> {
+        *self.0 = value.to_string().into();
+        Ok(())
+    }
+
+    fn visit_null(&mut self) -> Result<(), kv::Error> {
+        *self.0 = JsonValue::Null;
+        Ok(())
+    }
+
+    fn visit_u64(&mut self, value: u64) -> Result<(), kv::Error> {
+        *self.0 = value.into();
+        Ok(())
+    }
+
+    fn visit_i64(&mut self, value: i64) -> Result<(), kv::Error> {
+        *self.0 = value.into();
+        Ok(())
+    }
+
+    fn visit_f64(&mut self, value: f64) -> Result<(), kv::Error> {
+        *self.0 = value.into();
+        Ok(())
+    }
+
+    fn visit_bool(&mut self, value: bool) -> Result<(), kv::Error> {
+        *self.0 = value.into();
+        Ok(())
+    }
+
+    fn visit_str(&mut self, value: &str) -> Result<(), kv::Error> {
+        *self.0 = value.into();
+        Ok(())
+    }
+}
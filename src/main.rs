@@ -13,21 +13,25 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod logging;
+mod outdated;
 mod tests;
-mod versions;
 
 use clap::Parser;
-use env_logger::Env;
+use logging::{init_logger, LogFormat};
+use outdated::{outdated, OutputFormat};
+use psvm::versions::{
+    build_client, get_orml_crates_and_version, get_polkadot_sdk_versions,
+    get_release_branches_versions, get_version_mapping_with_fallback,
+    include_orml_crates_in_version_mapping, Repository, DEFAULT_MAX_PAGES, DEFAULT_USER_AGENT,
+};
+use similar::TextDiff;
 use std::{
     collections::BTreeMap,
     fs,
     path::{Path, PathBuf},
 };
 use toml_edit::DocumentMut;
-use versions::{
-    get_orml_crates_and_version, get_polkadot_sdk_versions, get_release_branches_versions,
-    get_version_mapping_with_fallback, include_orml_crates_in_version_mapping, Repository,
-};
 
 pub const DEFAULT_GIT_SERVER: &str = "https://raw.githubusercontent.com";
 
@@ -35,43 +39,121 @@ pub const DEFAULT_GIT_SERVER: &str = "https://raw.githubusercontent.com";
 ///
 /// Updates Cargo.toml dependencies based on Polkadot SDK crates.io release branch.
 #[derive(Parser, Debug)]
-#[command(about, author)]
+#[command(about, author, subcommand_negates_reqs = true)]
 struct Command {
+    #[command(subcommand)]
+    subcommand: Option<Subcommand>,
+
     /// Path to a crate folder or Cargo.toml file.
-    #[clap(short, long, default_value = "Cargo.toml")]
+    #[clap(short, long, global = true, default_value = "Cargo.toml")]
     path: PathBuf,
 
     /// Specifies the Polkadot SDK version. Use '--list' flag to display available versions.
-    #[clap(short, long, required_unless_present = "list")]
+    ///
+    /// Falls back to the `PSVM_VERSION` environment variable when the flag is not supplied, and
+    /// fails when neither is set or the value is empty.
+    #[clap(
+        short,
+        long,
+        env = "PSVM_VERSION",
+        required_unless_present = "list",
+        value_parser = clap::builder::NonEmptyStringValueParser::new()
+    )]
     version: Option<String>,
 
     /// Overwrite local dependencies (using path) with same name as the ones in the Polkadot SDK.
     #[clap(short, long)]
     overwrite: bool,
 
+    /// Only update the dependencies currently pinned to a git repository (using git, branch or rev),
+    /// leaving the already versioned ones untouched.
+    #[clap(long)]
+    only_git: bool,
+
     /// List available versions.
     #[clap(short, long)]
     list: bool,
 
+    /// Also list the pre-release stable tags (e.g. 'polkadot-stable2407-rc1').
+    #[clap(long, requires = "list")]
+    include_prereleases: bool,
+
     /// Check if the dependencies versions match the Polkadot SDK version. Does not update the Cargo.toml
     #[clap(short, long)]
     check: bool,
 
+    /// Only report the mismatches found by '--check', without failing.
+    #[clap(long, requires = "check")]
+    soft: bool,
+
+    /// Print the unified diff that would fix the mismatches found by '--check'.
+    #[clap(long, requires = "check")]
+    diff: bool,
+
     /// To either list available ORML versions or update the Cargo.toml file with corresponding ORML versions.
     #[clap(short('O'), long)]
     orml: bool,
+
+    /// Base URL of the server serving the raw content of the repositories (e.g. a caching proxy or
+    /// a GitHub Enterprise instance).
+    #[clap(long, global = true, default_value = DEFAULT_GIT_SERVER)]
+    git_server: String,
+
+    /// Value of the `User-Agent` header sent with every request.
+    #[clap(long, global = true, default_value = DEFAULT_USER_AGENT)]
+    user_agent: String,
+
+    /// Maximum number of pages fetched from the GitHub API when listing versions.
+    #[clap(
+        long,
+        global = true,
+        default_value_t = DEFAULT_MAX_PAGES,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    max_pages: u32,
+
+    /// Fail instead of only warning when the resolved version mapping is empty.
+    #[clap(long)]
+    fail_on_empty: bool,
+
+    /// Format of the log output. Use 'json' to emit one structured JSON object per line.
+    #[clap(long, global = true, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Subcommand {
+    /// Report whether a newer Polkadot SDK release is available for a Cargo.toml file.
+    Outdated {
+        /// Format of the output. Use 'json' to print a single JSON object.
+        #[clap(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
     let cmd = Command::parse();
+    init_logger(cmd.log_format);
+    let client = build_client(&cmd.user_agent)?;
+
+    if let Some(Subcommand::Outdated { format }) = cmd.subcommand {
+        let cargo_toml_path = validate_workspace_path(cmd.path)?;
+        return outdated(
+            &client,
+            &cargo_toml_path,
+            &cmd.git_server,
+            cmd.max_pages,
+            format,
+        )
+        .await;
+    }
 
     if cmd.list {
         let crates_versions = if cmd.orml {
-            get_release_branches_versions(Repository::Orml).await?
+            get_release_branches_versions(&client, Repository::Orml, cmd.max_pages).await?
         } else {
-            get_polkadot_sdk_versions().await?
+            get_polkadot_sdk_versions(&client, cmd.max_pages, cmd.include_prereleases).await?
         };
 
         println!("Available versions:");
@@ -87,18 +169,127 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Decide which branch data to use based on the branch name
     let mut crates_versions: BTreeMap<String, String> =
-        get_version_mapping_with_fallback(DEFAULT_GIT_SERVER, &version).await?;
+        get_version_mapping_with_fallback(&client, &cmd.git_server, &version).await?;
+
+    // An empty mapping almost always means a broken fetch or a bad version
+    if crates_versions.is_empty() {
+        if cmd.fail_on_empty {
+            return Err(format!("No crates versions found for version {}", version).into());
+        }
+        log::warn!(version = version.as_str(); "No crates versions found for version {}", version);
+    }
 
     if cmd.orml {
-        let orml_crates = get_orml_crates_and_version(DEFAULT_GIT_SERVER, &version).await?;
+        let orml_crates =
+            get_orml_crates_and_version(&client, &cmd.git_server, &version, cmd.max_pages).await?;
         include_orml_crates_in_version_mapping(&mut crates_versions, orml_crates);
     }
 
-    update_dependencies(&cargo_toml_path, &crates_versions, cmd.overwrite, cmd.check)?;
+    if cmd.check {
+        let report = check_dependencies(
+            &cargo_toml_path,
+            &crates_versions,
+            cmd.overwrite,
+            cmd.only_git,
+        )?;
+        report.print(&cargo_toml_path);
+        if cmd.diff && !report.is_up_to_date() {
+            print!("{}", report.diff(&cargo_toml_path));
+        }
+        report.ensure_up_to_date(cmd.soft)?;
+    } else {
+        update_dependencies(
+            &cargo_toml_path,
+            &crates_versions,
+            cmd.overwrite,
+            cmd.only_git,
+        )?;
+    }
 
     Ok(())
 }
 
+/// A dependency whose version doesn't match the one expected by the Polkadot SDK version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// The name of the crate (the `package` name for renamed dependencies).
+    pub crate_name: String,
+    /// The version expected by the Polkadot SDK version.
+    pub expected: String,
+    /// The version currently set, if any (e.g. `None` for git or path dependencies).
+    pub found: Option<String>,
+    /// The local path the dependency points to, if any (only replaced with `overwrite`).
+    pub path: Option<String>,
+}
+
+/// The outcome of checking a Cargo.toml file against a Polkadot SDK version.
+#[derive(Debug)]
+pub struct CheckReport {
+    /// The dependencies that are not up to date.
+    pub mismatches: Vec<Mismatch>,
+    /// The content of the Cargo.toml file.
+    original_content: String,
+    /// The content of the Cargo.toml file with all the dependencies up to date.
+    updated_content: String,
+}
+
+impl CheckReport {
+    /// Returns `true` if all the dependencies are up to date.
+    pub fn is_up_to_date(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    /// Renders the unified diff that brings the Cargo.toml file up to date, empty if already
+    /// up to date.
+    fn diff(&self, cargo_toml_path: &Path) -> String {
+        // Absolute paths are prefixed like relative ones (`a/tmp/...`, not `a//tmp/...`)
+        let path = cargo_toml_path.display().to_string();
+        let path = path.trim_start_matches('/');
+
+        TextDiff::from_lines(&self.original_content, &self.updated_content)
+            .unified_diff()
+            .header(&format!("a/{}", path), &format!("b/{}", path))
+            .to_string()
+    }
+
+    /// Logs every mismatch, followed by a summary line.
+    fn print(&self, cargo_toml_path: &Path) {
+        for mismatch in &self.mismatches {
+            log::error!(
+                crate = mismatch.crate_name.as_str(),
+                expected = mismatch.expected.as_str(),
+                found = mismatch.found.as_deref();
+                "Dependency version mismatch for {}: expected {}, found {}",
+                mismatch.crate_name,
+                mismatch.expected,
+                mismatch.found.as_deref().unwrap_or("none")
+            );
+        }
+
+        if self.is_up_to_date() {
+            println!(
+                "Dependencies in {} are up to date",
+                cargo_toml_path.display()
+            );
+        } else {
+            println!(
+                "Found {} outdated dependencies in {}",
+                self.mismatches.len(),
+                cargo_toml_path.display()
+            );
+        }
+    }
+
+    /// Returns an error if any dependency is not up to date, unless `soft` is set.
+    fn ensure_up_to_date(&self, soft: bool) -> Result<(), Box<dyn std::error::Error>> {
+        if self.is_up_to_date() || soft {
+            Ok(())
+        } else {
+            Err("Dependencies are not up to date".into())
+        }
+    }
+}
+
 fn validate_workspace_path(mut path: PathBuf) -> Result<PathBuf, Box<dyn std::error::Error>> {
     if path.is_dir() {
         path = path.join("Cargo.toml");
@@ -119,10 +310,10 @@ fn update_dependencies(
     cargo_toml_path: &Path,
     crates_versions: &BTreeMap<String, String>,
     overwrite: bool,
-    only_check: bool,
+    only_git: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let cargo_toml =
-        update_dependencies_impl(cargo_toml_path, crates_versions, overwrite, only_check)?;
+        update_dependencies_impl(cargo_toml_path, crates_versions, overwrite, only_git)?;
 
     match cargo_toml {
         Some(new_content) => {
@@ -144,39 +335,138 @@ fn update_dependencies_impl(
     cargo_toml_path: &Path,
     crates_versions: &BTreeMap<String, String>,
     overwrite: bool,
-    only_check: bool,
+    only_git: bool,
 ) -> Result<Option<String>, Box<dyn std::error::Error>> {
     let cargo_toml_content = fs::read_to_string(cargo_toml_path)?;
     let mut cargo_toml: DocumentMut = cargo_toml_content.parse()?;
-    // Check if cargo workspace is defined
-    let deps = match cargo_toml.as_table_mut().get_mut("workspace") {
-        Some(toml_edit::Item::Table(table)) => table,
-        _ => cargo_toml.as_table_mut(),
-    };
-
-    for table in ["dependencies", "dev-dependencies", "build-dependencies"].iter() {
-        if let Some(toml_edit::Item::Table(dep_table)) = deps.get_mut(table) {
-            update_table_dependencies(dep_table, crates_versions, overwrite);
+    let mismatches =
+        update_document_dependencies(&mut cargo_toml, crates_versions, overwrite, only_git);
+
+    for mismatch in &mismatches {
+        if let Some(path) = &mismatch.path {
+            log::warn!(
+                crate = mismatch.crate_name.as_str(),
+                path = path.as_str(),
+                expected = mismatch.expected.as_str();
+                "Overwriting local dependency {} (path = {}) with version {}",
+                mismatch.crate_name,
+                path,
+                mismatch.expected
+            );
         }
     }
 
-    let new_content = cargo_toml.to_string();
+    let new_content = preserve_line_endings(&cargo_toml_content, cargo_toml.to_string());
     if new_content != cargo_toml_content {
-        if only_check {
-            Err("Dependencies are not up to date".into())
-        } else {
-            Ok(Some(new_content))
-        }
+        Ok(Some(new_content))
     } else {
         Ok(None)
     }
 }
 
+/// Makes `new_content` use the same line endings (LF or CRLF) as `original`, and end with a
+/// newline only if `original` does, so the only differences are the updated dependencies.
+fn preserve_line_endings(original: &str, new_content: String) -> String {
+    let mut new_content = new_content.replace("\r\n", "\n");
+
+    if original.ends_with('\n') && !new_content.ends_with('\n') {
+        new_content.push('\n');
+    } else if !original.ends_with('\n') {
+        while new_content.ends_with('\n') {
+            new_content.pop();
+        }
+    }
+
+    if original.contains("\r\n") {
+        new_content.replace('\n', "\r\n")
+    } else {
+        new_content
+    }
+}
+
+fn check_dependencies(
+    cargo_toml_path: &Path,
+    crates_versions: &BTreeMap<String, String>,
+    overwrite: bool,
+    only_git: bool,
+) -> Result<CheckReport, Box<dyn std::error::Error>> {
+    let cargo_toml_content = fs::read_to_string(cargo_toml_path)?;
+    let mut cargo_toml: DocumentMut = cargo_toml_content.parse()?;
+    let mismatches =
+        update_document_dependencies(&mut cargo_toml, crates_versions, overwrite, only_git);
+
+    let updated_content = preserve_line_endings(&cargo_toml_content, cargo_toml.to_string());
+
+    Ok(CheckReport {
+        mismatches,
+        original_content: cargo_toml_content,
+        updated_content,
+    })
+}
+
+fn update_document_dependencies(
+    cargo_toml: &mut DocumentMut,
+    crates_versions: &BTreeMap<String, String>,
+    overwrite: bool,
+    only_git: bool,
+) -> Vec<Mismatch> {
+    let mut mismatches = vec![];
+
+    // Both the workspace and the root tables are updated, as a manifest can define a workspace
+    // (possibly without any dependencies) next to its own package dependencies
+    if let Some(toml_edit::Item::Table(workspace)) = cargo_toml.as_table_mut().get_mut("workspace")
+    {
+        mismatches.extend(update_dependency_tables(
+            workspace,
+            crates_versions,
+            overwrite,
+            only_git,
+        ));
+    }
+    mismatches.extend(update_dependency_tables(
+        cargo_toml.as_table_mut(),
+        crates_versions,
+        overwrite,
+        only_git,
+    ));
+
+    mismatches
+}
+
+fn update_dependency_tables(
+    deps: &mut toml_edit::Table,
+    crates_versions: &BTreeMap<String, String>,
+    overwrite: bool,
+    only_git: bool,
+) -> Vec<Mismatch> {
+    let mut mismatches = vec![];
+    for table in ["dependencies", "dev-dependencies", "build-dependencies"].iter() {
+        if let Some(toml_edit::Item::Table(dep_table)) = deps.get_mut(table) {
+            mismatches.extend(update_table_dependencies(
+                dep_table,
+                crates_versions,
+                overwrite,
+                only_git,
+            ));
+        }
+    }
+
+    mismatches
+}
+
+/// Updates the dependencies of `dep_table` to the versions in `crates_versions`, returning the
+/// dependencies whose version didn't match.
+///
+/// With `only_git`, the dependencies not pinned to a git repository are left untouched.
+/// Dependencies inherited from the workspace (`workspace = true`) are always left untouched.
 pub fn update_table_dependencies(
     dep_table: &mut toml_edit::Table,
     crates_versions: &BTreeMap<String, String>,
     overwrite: bool,
-) {
+    only_git: bool,
+) -> Vec<Mismatch> {
+    let mut mismatches = vec![];
+
     for (dep_key, dep_value) in dep_table.iter_mut() {
         let dep_key_str = dep_key.get();
 
@@ -188,17 +478,55 @@ pub fn update_table_dependencies(
                 .unwrap_or(dep_key_str)
         } else {
             dep_key_str
-        };
+        }
+        .to_string();
+
+        // Inherited dependencies get their version from the workspace table, updated on its own
+        let inherited = dep_value
+            .as_table_like()
+            .and_then(|table| table.get("workspace"))
+            .and_then(|workspace| workspace.as_bool())
+            .unwrap_or(false);
+        if inherited {
+            continue;
+        }
 
-        let Some(crate_version) = crates_versions.get(lookup_key) else {
-            log::debug!("Could not find version for {}", lookup_key);
+        let Some(crate_version) = crates_versions.get(&lookup_key) else {
+            log::debug!(crate = lookup_key.as_str(); "Could not find version for {}", lookup_key);
             continue;
         };
 
-        if let Some(table) = dep_value.as_table_like_mut() {
-            if !overwrite && table.get("path").is_some() {
+        if only_git {
+            let git_pinned = dep_value.as_table_like().is_some_and(|table| {
+                ["git", "branch", "rev"]
+                    .iter()
+                    .any(|key| table.contains_key(key))
+            });
+            if !git_pinned {
                 continue;
             }
+        }
+
+        let found_version = match dep_value.as_table_like() {
+            Some(table) => table.get("version").and_then(|v| v.as_str()),
+            None => dep_value.as_str(),
+        }
+        .map(str::to_string);
+        let mut pinned = false;
+        let mut local_path = None;
+
+        if let Some(table) = dep_value.as_table_like_mut() {
+            if let Some(path) = table.get("path") {
+                if !overwrite {
+                    continue;
+                }
+
+                local_path = Some(path.as_str().unwrap_or_default().to_string());
+            }
+
+            pinned = ["rev", "branch", "tag", "path", "git"]
+                .iter()
+                .any(|key| table.contains_key(key));
 
             table.remove("rev");
             table.remove("branch");
@@ -224,13 +552,36 @@ pub fn update_table_dependencies(
 
             // Replace the original table-like item with the new inline table
             *dep_value = toml_edit::Item::Value(toml_edit::Value::InlineTable(new_table));
-        } else if dep_value.is_str() {
-            *dep_value = toml_edit::value(crate_version.clone());
+        } else if let Some(value) = dep_value.as_value_mut().filter(|value| value.is_str()) {
+            // Only touch the version itself, keeping the whitespace and comments around it
+            if found_version.as_deref() != Some(crate_version.as_str()) {
+                let decor = value.decor().clone();
+                *value = toml_edit::Value::from(crate_version.as_str());
+                *value.decor_mut() = decor;
+            }
         } else {
-            log::error!("Unexpected dependency value type for {}", dep_key_str);
+            log::error!(crate = dep_key_str; "Unexpected dependency value type for {}", dep_key_str);
             continue;
         }
 
-        log::debug!("Setting {} to {}", dep_key_str, crate_version);
+        log::debug!(
+            crate = lookup_key.as_str(),
+            expected = crate_version.as_str(),
+            found = found_version.as_deref();
+            "Setting {} to {}",
+            dep_key_str,
+            crate_version
+        );
+
+        if pinned || found_version.as_deref() != Some(crate_version.as_str()) {
+            mismatches.push(Mismatch {
+                crate_name: lookup_key,
+                expected: crate_version.clone(),
+                found: found_version,
+                path: local_path,
+            });
+        }
     }
+
+    mismatches
 }
@@ -0,0 +1,224 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use psvm::versions::{
+    get_parity_crate_owner_crates, get_polkadot_sdk_versions, get_version_mapping_with_owners,
+};
+use std::{collections::BTreeMap, fs, path::Path};
+use toml_edit::DocumentMut;
+
+/// The format used to print the result of the `outdated` command.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// A human readable sentence.
+    #[default]
+    Text,
+    /// A single JSON object, for bots.
+    Json,
+}
+
+/// How far a Cargo.toml file is from the latest Polkadot SDK release.
+#[derive(Debug, PartialEq, Eq)]
+pub struct OutdatedReport {
+    /// The Polkadot SDK version the dependencies currently match.
+    pub current: String,
+    /// The latest Polkadot SDK release.
+    pub latest: String,
+    /// The number of releases between `current` and `latest`.
+    pub behind: usize,
+}
+
+impl OutdatedReport {
+    /// Builds the report for `current` out of all the releases, sorted from oldest to latest.
+    ///
+    /// Returns `None` if `current` isn't one of the releases.
+    pub fn new(current: &str, sorted_versions: &[String]) -> Option<Self> {
+        let position = sorted_versions.iter().position(|v| v == current)?;
+        let latest = sorted_versions.last()?;
+
+        Some(Self {
+            current: current.to_string(),
+            latest: latest.clone(),
+            behind: sorted_versions.len() - 1 - position,
+        })
+    }
+
+    fn print(&self, cargo_toml_path: &Path, format: OutputFormat) {
+        match format {
+            OutputFormat::Text if self.behind == 0 => println!(
+                "{}: on {}, which is the latest release",
+                cargo_toml_path.display(),
+                self.current
+            ),
+            OutputFormat::Text => println!(
+                "{}: on {}, latest is {}, {} release{} behind",
+                cargo_toml_path.display(),
+                self.current,
+                self.latest,
+                self.behind,
+                if self.behind == 1 { "" } else { "s" }
+            ),
+            OutputFormat::Json => println!(
+                "{}",
+                serde_json::json!({
+                    "path": cargo_toml_path.display().to_string(),
+                    "current": self.current,
+                    "latest": self.latest,
+                    "behind": self.behind,
+                })
+            ),
+        }
+    }
+}
+
+/// Reports whether a newer Polkadot SDK release is available for the given Cargo.toml file.
+///
+/// The current version is detected by looking for the latest release whose version mapping
+/// matches every Polkadot SDK dependency of the file.
+///
+/// The releases are fetched one at a time from the latest, each with up to two requests (its
+/// `Plan.toml`, then its `Cargo.lock` file), so detecting a release far behind the latest one
+/// takes as many requests. Releases without any of these files are skipped.
+///
+/// # Errors
+/// This function returns an error if the versions or any of their mappings can't be fetched,
+/// if the Cargo.toml file can't be read, or if its Polkadot SDK version can't be detected.
+pub async fn outdated(
+    client: &reqwest::Client,
+    cargo_toml_path: &Path,
+    base_url: &str,
+    max_pages: u32,
+    format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut versions = get_polkadot_sdk_versions(client, max_pages, false).await?;
+    sort_versions(&mut versions);
+
+    let dependencies = get_manifest_dependencies(cargo_toml_path)?;
+
+    // Fetched once, as every Plan.toml mapping needs it
+    let parity_owned_crates = get_parity_crate_owner_crates(client).await?;
+
+    let mut current = None;
+    for version in versions.iter().rev() {
+        let Some(crates_versions) =
+            get_version_mapping_with_owners(client, base_url, version, &parity_owned_crates)
+                .await?
+        else {
+            log::debug!(
+                "Skipping version {}: no Plan.toml or Cargo.lock found",
+                version
+            );
+            continue;
+        };
+
+        if matches_version_mapping(&dependencies, &crates_versions) {
+            current = Some(version);
+            break;
+        }
+    }
+
+    let report = current
+        .and_then(|current| OutdatedReport::new(current, &versions))
+        .ok_or_else(|| {
+            format!(
+                "Could not detect the Polkadot SDK version of {}",
+                cargo_toml_path.display()
+            )
+        })?;
+
+    report.print(cargo_toml_path, format);
+
+    Ok(())
+}
+
+/// Sorts the versions from oldest to latest.
+///
+/// Release versions (e.g. `1.14.0`) come before stable tags (e.g. `polkadot-stable2407-1`),
+/// and each group is ordered by its numeric components.
+pub fn sort_versions(versions: &mut [String]) {
+    versions.sort_by_cached_key(|version| {
+        let (stable, numbers) = match version.strip_prefix("polkadot-stable") {
+            Some(numbers) => (true, numbers),
+            None => (false, version.as_str()),
+        };
+
+        let numbers: Vec<u64> = numbers
+            .split(|c: char| !c.is_ascii_digit())
+            .filter_map(|n| n.parse().ok())
+            .collect();
+
+        (stable, numbers)
+    });
+}
+
+/// Returns `true` if at least one dependency is in the mapping, and all those that are
+/// match the mapping version.
+pub fn matches_version_mapping(
+    dependencies: &BTreeMap<String, String>,
+    crates_versions: &BTreeMap<String, String>,
+) -> bool {
+    let mut matched = false;
+
+    for (name, version) in dependencies {
+        if let Some(expected) = crates_versions.get(name) {
+            if expected != version {
+                return false;
+            }
+            matched = true;
+        }
+    }
+
+    matched
+}
+
+/// Collects the versioned dependencies of the workspace and root tables of a Cargo.toml file.
+fn get_manifest_dependencies(
+    cargo_toml_path: &Path,
+) -> Result<BTreeMap<String, String>, Box<dyn std::error::Error>> {
+    let cargo_toml: DocumentMut = fs::read_to_string(cargo_toml_path)?.parse()?;
+
+    let mut tables = vec![cargo_toml.as_table()];
+    if let Some(toml_edit::Item::Table(workspace)) = cargo_toml.get("workspace") {
+        tables.push(workspace);
+    }
+
+    let mut dependencies = BTreeMap::new();
+    for deps in tables {
+        for table in ["dependencies", "dev-dependencies", "build-dependencies"].iter() {
+            let Some(toml_edit::Item::Table(dep_table)) = deps.get(table) else {
+                continue;
+            };
+
+            for (dep_key, dep_value) in dep_table.iter() {
+                let (name, version) = match dep_value.as_table_like() {
+                    Some(table) => (
+                        table
+                            .get("package")
+                            .and_then(|p| p.as_str())
+                            .unwrap_or(dep_key),
+                        table.get("version").and_then(|v| v.as_str()),
+                    ),
+                    None => (dep_key, dep_value.as_str()),
+                };
+
+                if let Some(version) = version {
+                    dependencies.insert(name.to_string(), version.to_string());
+                }
+            }
+        }
+    }
+
+    Ok(dependencies)
+}
@@ -15,20 +15,77 @@
 
 #[cfg(test)]
 mod tests {
-    use crate::versions::get_orml_crates_and_version;
-    use crate::versions::get_version_mapping_with_fallback;
-    use crate::versions::include_orml_crates_in_version_mapping;
-    use crate::versions::Repository;
-    use std::{error::Error, path::Path};
+    use crate::logging::key_values_to_json;
+    use crate::outdated::{matches_version_mapping, sort_versions, OutdatedReport};
+    use crate::Mismatch;
+    use clap::Parser;
+    use log::kv::ToValue;
+    use psvm::versions::build_client;
+    use psvm::versions::get_orml_crates_and_version;
+    use psvm::versions::get_plan_packages;
+    use psvm::versions::get_version_mapping;
+    use psvm::versions::get_version_mapping_with_fallback;
+    use psvm::versions::get_version_mapping_with_owners;
+    use psvm::versions::include_orml_crates_in_version_mapping;
+    use psvm::versions::Repository;
+    use psvm::versions::DEFAULT_MAX_PAGES;
+    use psvm::versions::DEFAULT_USER_AGENT;
+    use std::{
+        collections::{BTreeMap, HashSet},
+        error::Error,
+        path::{Path, PathBuf},
+        sync::{Mutex, Once},
+    };
+
+    /// Records the messages of every log record emitted by the tests, so they can be asserted.
+    struct CapturingLogger;
+
+    static CAPTURED_LOGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            CAPTURED_LOGS
+                .lock()
+                .unwrap()
+                .push(format!("{} {}", record.level(), record.args()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    fn init_capturing_logger() {
+        static INIT: Once = Once::new();
+        INIT.call_once(|| {
+            log::set_logger(&CapturingLogger).unwrap();
+            log::set_max_level(log::LevelFilter::Trace);
+        });
+    }
+
+    fn captured_logs_contain(message: &str) -> bool {
+        CAPTURED_LOGS
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|log| log == message)
+    }
+
+    fn test_client() -> reqwest::Client {
+        build_client(DEFAULT_USER_AGENT).unwrap()
+    }
 
     async fn verify_version_mapping(
         version: &str,
         input_cargo_toml_path: &Path,
         expected_cargo_toml: &str,
     ) {
-        let crates_versions = get_version_mapping_with_fallback(crate::DEFAULT_GIT_SERVER, version)
-            .await
-            .unwrap();
+        let crates_versions =
+            get_version_mapping_with_fallback(&test_client(), crate::DEFAULT_GIT_SERVER, version)
+                .await
+                .unwrap();
 
         // Call the refactored logic function with the test data
         let result =
@@ -42,21 +99,24 @@ mod tests {
     async fn verify_version_checking(
         version: &str,
         input_cargo_toml_path: &Path,
-    ) -> Result<Option<String>, Box<dyn Error>> {
+    ) -> Result<(), Box<dyn Error>> {
         let mut crates_versions =
-            get_version_mapping_with_fallback(crate::DEFAULT_GIT_SERVER, version)
+            get_version_mapping_with_fallback(&test_client(), crate::DEFAULT_GIT_SERVER, version)
                 .await
                 .unwrap();
 
-        let orml_crates_version =
-            get_orml_crates_and_version(crate::DEFAULT_GIT_SERVER, &version).await?;
+        let orml_crates_version = get_orml_crates_and_version(
+            &test_client(),
+            crate::DEFAULT_GIT_SERVER,
+            &version,
+            DEFAULT_MAX_PAGES,
+        )
+        .await?;
         include_orml_crates_in_version_mapping(&mut crates_versions, orml_crates_version);
 
         // Call the refactored logic function with the test data
-        let result =
-            crate::update_dependencies_impl(&input_cargo_toml_path, &crates_versions, false, true);
-
-        result
+        crate::check_dependencies(&input_cargo_toml_path, &crates_versions, false, false)?
+            .ensure_up_to_date(false)
     }
 
     async fn verify_orml_version_mapping(
@@ -65,13 +125,18 @@ mod tests {
         expected_cargo_toml: &str,
     ) {
         let mut crates_versions =
-            get_version_mapping_with_fallback(crate::DEFAULT_GIT_SERVER, version)
+            get_version_mapping_with_fallback(&test_client(), crate::DEFAULT_GIT_SERVER, version)
                 .await
                 .unwrap();
 
-        let orml_crates_version = get_orml_crates_and_version(crate::DEFAULT_GIT_SERVER, &version)
-            .await
-            .unwrap();
+        let orml_crates_version = get_orml_crates_and_version(
+            &test_client(),
+            crate::DEFAULT_GIT_SERVER,
+            &version,
+            DEFAULT_MAX_PAGES,
+        )
+        .await
+        .unwrap();
         include_orml_crates_in_version_mapping(&mut crates_versions, orml_crates_version);
 
         // Call the refactored logic function with the test data
@@ -104,7 +169,6 @@ mod tests {
 
         let res = verify_version_checking(version, input_cargo_toml_path).await;
         assert!(res.is_ok());
-        assert!(res.unwrap().is_none());
     }
 
     #[tokio::test]
@@ -118,7 +182,6 @@ mod tests {
 
         let res = verify_version_checking(version, input_cargo_toml_path).await;
         assert!(res.is_ok());
-        assert!(res.unwrap().is_none());
     }
 
     #[tokio::test]
@@ -149,7 +212,6 @@ mod tests {
 
         let res = verify_version_checking(version, input_cargo_toml_path).await;
         assert!(res.is_ok());
-        assert!(res.unwrap().is_none());
     }
 
     #[tokio::test]
@@ -199,6 +261,440 @@ mod tests {
         verify_version_mapping(version, input_cargo_toml_path, output_cargo_toml_path).await;
     }
 
+    #[test]
+    // cargo psvm -v N.N.N -c --soft
+    // The drift is reported, but the check doesn't fail
+    fn test_soft_check_passes_with_outdated_dependencies() {
+        let input_cargo_toml_path = Path::new("src/testing/plan-toml/check.Cargo.toml");
+        let crates_versions = BTreeMap::from([
+            ("frame-support".to_string(), "36.0.0".to_string()),
+            ("frame-system".to_string(), "37.0.0".to_string()),
+        ]);
+
+        let report =
+            crate::check_dependencies(input_cargo_toml_path, &crates_versions, false, false)
+                .unwrap();
+
+        assert_eq!(
+            report.mismatches,
+            vec![Mismatch {
+                crate_name: "frame-system".to_string(),
+                expected: "37.0.0".to_string(),
+                found: Some("36.1.0".to_string()),
+                path: None,
+            }]
+        );
+        assert!(report.ensure_up_to_date(true).is_ok());
+        assert_eq!(
+            report.ensure_up_to_date(false).unwrap_err().to_string(),
+            "Dependencies are not up to date"
+        );
+    }
+
+    #[test]
+    // cargo psvm -v N.N.N --only-git
+    // Only the dependencies pinned to git are updated, the versioned ones are left untouched
+    // even if their version differs from the mapping
+    fn test_only_git_updates_only_git_dependencies() {
+        let input_cargo_toml_path = Path::new("src/testing/only-git/input.Cargo.toml");
+        let expected_cargo_toml = include_str!("testing/only-git/output.Cargo.toml");
+        let crates_versions = BTreeMap::from(
+            [
+                ("substrate-wasm-builder", "17.0.0"),
+                ("parity-scale-codec", "3.6.9"),
+                ("frame-support", "28.0.0"),
+                ("frame-system", "28.0.0"),
+                ("sp-core", "29.0.0"),
+                ("sp-io", "31.0.0"),
+                ("sp-runtime", "31.0.1"),
+                ("sp-tracing", "17.0.0"),
+            ]
+            .map(|(name, version)| (name.to_string(), version.to_string())),
+        );
+
+        let result =
+            crate::update_dependencies_impl(input_cargo_toml_path, &crates_versions, false, true)
+                .unwrap();
+
+        assert_eq!(result, Some(expected_cargo_toml.into()));
+    }
+
+    #[test]
+    // cargo psvm -v N.N.N -o
+    // The local path dependency is converted to a crates.io version, and a warning is logged
+    fn test_overwrite_warns_for_path_dependencies() {
+        init_capturing_logger();
+
+        let input_cargo_toml_path = Path::new("src/testing/overwrite/input.Cargo.toml");
+        let expected_cargo_toml = include_str!("testing/overwrite/output.Cargo.toml");
+        let crates_versions = BTreeMap::from([
+            ("pallet-parachain-template".to_string(), "0.7.0".to_string()),
+            ("frame-support".to_string(), "29.0.0".to_string()),
+        ]);
+
+        let result =
+            crate::update_dependencies_impl(input_cargo_toml_path, &crates_versions, true, false)
+                .unwrap();
+
+        assert_eq!(result, Some(expected_cargo_toml.into()));
+        assert!(captured_logs_contain(
+            "WARN Overwriting local dependency pallet-parachain-template (path = ../pallets/template) with version 0.7.0"
+        ));
+        assert!(!captured_logs_contain(
+            "WARN Overwriting local dependency frame-support (path = ) with version 29.0.0"
+        ));
+    }
+
+    #[test]
+    // cargo psvm -v N.N.N -c -o
+    // The local path dependency is reported, but nothing is overwritten so no warning is logged
+    fn test_check_with_overwrite_does_not_warn() {
+        init_capturing_logger();
+
+        let input_cargo_toml_path = Path::new("src/testing/overwrite/input.Cargo.toml");
+        let crates_versions =
+            BTreeMap::from([("pallet-parachain-template".to_string(), "0.8.0".to_string())]);
+
+        let report =
+            crate::check_dependencies(input_cargo_toml_path, &crates_versions, true, false)
+                .unwrap();
+
+        assert_eq!(
+            report.mismatches,
+            vec![Mismatch {
+                crate_name: "pallet-parachain-template".to_string(),
+                expected: "0.8.0".to_string(),
+                found: None,
+                path: Some("../pallets/template".to_string()),
+            }]
+        );
+        assert!(!captured_logs_contain(
+            "WARN Overwriting local dependency pallet-parachain-template (path = ../pallets/template) with version 0.8.0"
+        ));
+    }
+
+    #[test]
+    // cargo psvm -v N.N.N
+    // A virtual workspace manifest has no dependencies to update, so it is left untouched
+    fn test_virtual_workspace_without_dependencies_is_noop() {
+        let input_cargo_toml_path = Path::new("src/testing/workspace/virtual.Cargo.toml");
+        let crates_versions = BTreeMap::from([("frame-support".to_string(), "29.0.0".to_string())]);
+
+        let result =
+            crate::update_dependencies_impl(input_cargo_toml_path, &crates_versions, false, false)
+                .unwrap();
+        assert_eq!(result, None);
+
+        let report =
+            crate::check_dependencies(input_cargo_toml_path, &crates_versions, false, false)
+                .unwrap();
+        assert!(report.is_up_to_date());
+    }
+
+    #[test]
+    // cargo psvm -v N.N.N
+    // The workspace table has no dependencies, but the root package ones are still updated
+    fn test_workspace_without_dependencies_updates_root_dependencies() {
+        let input_cargo_toml_path = Path::new("src/testing/workspace/mixedInput.Cargo.toml");
+        let expected_cargo_toml = include_str!("testing/workspace/mixedOutput.Cargo.toml");
+        let crates_versions = BTreeMap::from([
+            ("frame-support".to_string(), "29.0.0".to_string()),
+            ("sp-core".to_string(), "29.0.0".to_string()),
+        ]);
+
+        let result =
+            crate::update_dependencies_impl(input_cargo_toml_path, &crates_versions, false, false)
+                .unwrap();
+
+        assert_eq!(result, Some(expected_cargo_toml.into()));
+    }
+
+    #[test]
+    // cargo psvm -v N.N.N (then with -c)
+    // Only the workspace dependencies are updated, those inheriting them with `workspace = true`
+    // are neither rewritten nor reported
+    fn test_workspace_inherited_dependencies_are_skipped() {
+        let input_cargo_toml_path = Path::new("src/testing/workspace/inheritedInput.Cargo.toml");
+        let output_cargo_toml_path = Path::new("src/testing/workspace/inheritedOutput.Cargo.toml");
+        let expected_cargo_toml = include_str!("testing/workspace/inheritedOutput.Cargo.toml");
+        let crates_versions = BTreeMap::from([
+            ("frame-support".to_string(), "29.0.0".to_string()),
+            ("sp-core".to_string(), "29.0.0".to_string()),
+        ]);
+
+        let result =
+            crate::update_dependencies_impl(input_cargo_toml_path, &crates_versions, false, false)
+                .unwrap();
+        assert_eq!(result, Some(expected_cargo_toml.into()));
+
+        let report =
+            crate::check_dependencies(output_cargo_toml_path, &crates_versions, false, false)
+                .unwrap();
+        assert!(report.is_up_to_date());
+    }
+
+    fn string_deps_versions() -> BTreeMap<String, String> {
+        BTreeMap::from(
+            [
+                ("frame-support", "29.0.0"),
+                ("frame-system", "29.0.0"),
+                ("sp-core", "29.0.0"),
+                ("sp-io", "31.0.0"),
+                ("sp-tracing", "17.0.0"),
+            ]
+            .map(|(name, version)| (name.to_string(), version.to_string())),
+        )
+    }
+
+    /// A temporary Cargo.toml file, removed when dropped.
+    struct TempCargoToml(PathBuf);
+
+    impl std::ops::Deref for TempCargoToml {
+        type Target = Path;
+
+        fn deref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempCargoToml {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    /// Writes `content` to a temporary Cargo.toml file, unique to this test process.
+    fn write_temp_cargo_toml(name: &str, content: &str) -> TempCargoToml {
+        let path =
+            std::env::temp_dir().join(format!("psvm-{}-{}.Cargo.toml", std::process::id(), name));
+        std::fs::write(&path, content).unwrap();
+        TempCargoToml(path)
+    }
+
+    #[test]
+    // cargo psvm -v N.N.N (twice)
+    // Only the outdated string dependencies change, keeping their whitespace and comments,
+    // and a second run makes no change at all
+    fn test_string_dependencies_update_is_idempotent() {
+        let input_cargo_toml_path = Path::new("src/testing/string-deps/input.Cargo.toml");
+        let output_cargo_toml_path = Path::new("src/testing/string-deps/output.Cargo.toml");
+        let expected_cargo_toml = include_str!("testing/string-deps/output.Cargo.toml");
+        let crates_versions = string_deps_versions();
+
+        let first_run =
+            crate::update_dependencies_impl(input_cargo_toml_path, &crates_versions, false, false)
+                .unwrap();
+        assert_eq!(first_run, Some(expected_cargo_toml.into()));
+
+        let second_run =
+            crate::update_dependencies_impl(output_cargo_toml_path, &crates_versions, false, false)
+                .unwrap();
+        assert_eq!(second_run, None);
+    }
+
+    #[test]
+    // cargo psvm -v N.N.N -c --diff
+    // The check fails, and the diff shows the minimal fix
+    fn test_check_with_diff_fails_and_shows_fix() {
+        let input_cargo_toml_path = Path::new("src/testing/string-deps/input.Cargo.toml");
+
+        let report =
+            crate::check_dependencies(input_cargo_toml_path, &string_deps_versions(), false, false)
+                .unwrap();
+
+        assert!(report.ensure_up_to_date(false).is_err());
+        assert_eq!(
+            report.diff(input_cargo_toml_path),
+            r#"--- a/src/testing/string-deps/input.Cargo.toml
++++ b/src/testing/string-deps/input.Cargo.toml
+@@ -7,10 +7,10 @@
+ log = "0.4.20"
+ 
+ # Substrate
+-frame-support =   "28.0.0"   # pinned for the runtime
++frame-support =   "29.0.0"   # pinned for the runtime
+ frame-system="29.0.0"
+ sp-core = "29.0.0" # already up to date
+-sp-io    = "30.0.0"
++sp-io    = "31.0.0"
+ 
+ [dev-dependencies]
+-sp-tracing = "16.0.0"
++sp-tracing = "17.0.0"
+"#
+        );
+    }
+
+    #[test]
+    // cargo psvm -v N.N.N -c --diff
+    // An up to date manifest has an empty diff, and an absolute path keeps a single slash
+    fn test_check_diff_headers_and_up_to_date_diff() {
+        let output_cargo_toml_path = Path::new("src/testing/string-deps/output.Cargo.toml");
+        let report = crate::check_dependencies(
+            output_cargo_toml_path,
+            &string_deps_versions(),
+            false,
+            false,
+        )
+        .unwrap();
+        assert!(report.is_up_to_date());
+        assert_eq!(report.diff(output_cargo_toml_path), "");
+
+        let input_cargo_toml_path = write_temp_cargo_toml(
+            "absolute-diff",
+            include_str!("testing/string-deps/input.Cargo.toml"),
+        );
+        let report = crate::check_dependencies(
+            &input_cargo_toml_path,
+            &string_deps_versions(),
+            false,
+            false,
+        )
+        .unwrap();
+        let path = input_cargo_toml_path.display().to_string();
+        let path = path.trim_start_matches('/');
+        assert!(report
+            .diff(&input_cargo_toml_path)
+            .starts_with(&format!("--- a/{}\n+++ b/{}\n", path, path)));
+    }
+
+    #[test]
+    // cargo psvm -v N.N.N
+    // A manifest with CRLF line endings keeps them after the update
+    fn test_update_preserves_crlf_line_endings() {
+        let to_crlf = |content: &str| content.replace('\n', "\r\n");
+        let input_cargo_toml_path = write_temp_cargo_toml(
+            "crlf",
+            &to_crlf(include_str!("testing/string-deps/input.Cargo.toml")),
+        );
+        let expected_cargo_toml = to_crlf(include_str!("testing/string-deps/output.Cargo.toml"));
+
+        let result = crate::update_dependencies_impl(
+            &input_cargo_toml_path,
+            &string_deps_versions(),
+            false,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(result, Some(expected_cargo_toml));
+    }
+
+    #[test]
+    // cargo psvm -v N.N.N
+    // A manifest without a trailing newline doesn't get one after the update
+    fn test_update_preserves_missing_trailing_newline() {
+        let input_cargo_toml_path = write_temp_cargo_toml(
+            "no-trailing-newline",
+            include_str!("testing/string-deps/input.Cargo.toml").trim_end(),
+        );
+        let expected_cargo_toml = include_str!("testing/string-deps/output.Cargo.toml").trim_end();
+
+        let result = crate::update_dependencies_impl(
+            &input_cargo_toml_path,
+            &string_deps_versions(),
+            false,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(result, Some(expected_cargo_toml.into()));
+    }
+
+    #[test]
+    // psvm --log-format json
+    // The primitive key-values keep their JSON type
+    fn test_key_values_to_json_keeps_primitive_types() {
+        let found: Option<&str> = None;
+        let key_values: [(&str, log::kv::Value); 5] = [
+            ("crate", "sp-core".into()),
+            ("max_pages", 3u32.into()),
+            ("soft", true.into()),
+            ("expected", Some("29.0.0").to_value()),
+            ("found", found.to_value()),
+        ];
+
+        assert_eq!(
+            serde_json::Value::Object(key_values_to_json(&key_values)),
+            serde_json::json!({
+                "crate": "sp-core",
+                "max_pages": 3,
+                "soft": true,
+                "expected": "29.0.0",
+                "found": null,
+            })
+        );
+    }
+
+    #[test]
+    // cargo psvm outdated
+    // The releases are sorted from oldest to latest, and the gap is counted in releases
+    fn test_outdated_report_counts_releases_behind() {
+        let mut versions = [
+            "1.14.0",
+            "polkadot-stable2407-1",
+            "1.3.0",
+            "polkadot-stable2407",
+            "1.12.0",
+            "1.13.0",
+        ]
+        .map(String::from)
+        .to_vec();
+        sort_versions(&mut versions);
+
+        assert_eq!(
+            versions,
+            vec![
+                "1.3.0",
+                "1.12.0",
+                "1.13.0",
+                "1.14.0",
+                "polkadot-stable2407",
+                "polkadot-stable2407-1"
+            ]
+        );
+        assert_eq!(
+            OutdatedReport::new("1.12.0", &versions),
+            Some(OutdatedReport {
+                current: "1.12.0".to_string(),
+                latest: "polkadot-stable2407-1".to_string(),
+                behind: 4,
+            })
+        );
+        assert_eq!(
+            OutdatedReport::new("polkadot-stable2407-1", &versions).map(|r| r.behind),
+            Some(0)
+        );
+        assert_eq!(OutdatedReport::new("1.0.0", &versions), None);
+    }
+
+    #[test]
+    fn test_matches_version_mapping() {
+        let crates_versions = BTreeMap::from([
+            ("frame-support".to_string(), "36.0.0".to_string()),
+            ("frame-system".to_string(), "36.1.0".to_string()),
+        ]);
+        let dependencies = |deps: &[(&str, &str)]| {
+            deps.iter()
+                .map(|(name, version)| (name.to_string(), version.to_string()))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        assert!(matches_version_mapping(
+            &dependencies(&[("frame-support", "36.0.0"), ("log", "0.4.20")]),
+            &crates_versions
+        ));
+        assert!(!matches_version_mapping(
+            &dependencies(&[("frame-support", "36.0.0"), ("frame-system", "36.0.0")]),
+            &crates_versions
+        ));
+        // No Polkadot SDK dependency at all
+        assert!(!matches_version_mapping(
+            &dependencies(&[("log", "0.4.20")]),
+            &crates_versions
+        ));
+    }
+
     #[tokio::test]
     async fn test_parse_version_mapping_from_plan_toml() {
         let response = r#"
@@ -239,7 +735,7 @@ publish = false
         .create();
 
         let git_server = &mockito::server_url();
-        let mapping = get_version_mapping_with_fallback(git_server, version)
+        let mapping = get_version_mapping_with_fallback(&test_client(), git_server, version)
             .await
             .unwrap();
 
@@ -248,6 +744,49 @@ publish = false
         assert_eq!(mapping.get("package_major"), Some(&"2.0.0".to_string()));
     }
 
+    #[test]
+    // The parity-crate-owner crates are injected, so no request is made
+    fn test_get_plan_packages_keeps_parity_owned_crates() {
+        let content = r#"
+[[crate]]
+name = "package_published"
+from = "0.1.0"
+to = "0.2.0"
+
+[[crate]]
+name = "package_parity_owned"
+from = "1.0.0"
+to = "1.0.0"
+publish = false
+
+[[crate]]
+name = "package_placeholder"
+from = "0.0.0"
+to = "0.0.0"
+publish = false
+
+[[crate]]
+name = "package_no_publish"
+from = "0.1.0"
+to = "0.1.0"
+publish = false
+"#;
+        let parity_owned_crates = HashSet::from([
+            "package_parity_owned".to_string(),
+            "package_placeholder".to_string(),
+        ]);
+
+        let mapping = get_plan_packages(content, &parity_owned_crates).unwrap();
+
+        assert_eq!(
+            mapping,
+            BTreeMap::from([
+                ("package_parity_owned".to_string(), "1.0.0".to_string()),
+                ("package_published".to_string(), "0.2.0".to_string()),
+            ])
+        );
+    }
+
     #[tokio::test]
     async fn test_parse_version_mapping_from_cargo_lock() {
         let response = r#"
@@ -276,7 +815,7 @@ source = "registry+https://github.com/rust-lang/crates.io-index"
         .create();
 
         let git_server = &mockito::server_url();
-        let mapping = get_version_mapping_with_fallback(git_server, version)
+        let mapping = get_version_mapping_with_fallback(&test_client(), git_server, version)
             .await
             .unwrap();
 
@@ -284,6 +823,158 @@ source = "registry+https://github.com/rust-lang/crates.io-index"
         assert_eq!(mapping.get("local_package"), Some(&"0.1.0".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_requests_send_the_configured_user_agent() {
+        let response = r#"
+[[package]]
+name = "local_package"
+version = "0.1.0"
+"#;
+        let version = "user-agent";
+        let source = "Cargo.lock";
+
+        let m = mockito::mock(
+            "GET",
+            format!(
+                "/paritytech/polkadot-sdk/release-crates-io-v{}/{}",
+                version, source
+            )
+            .as_str(),
+        )
+        .match_header("User-Agent", "psvm-tests/1.0")
+        .with_status(200)
+        .with_body(response)
+        .expect(1)
+        .create();
+
+        let client = build_client("psvm-tests/1.0").unwrap();
+        let git_server = &mockito::server_url();
+        let mapping = get_version_mapping(&client, git_server, version, source)
+            .await
+            .unwrap();
+
+        m.assert();
+        assert_eq!(mapping.get("local_package"), Some(&"0.1.0".to_string()));
+    }
+
+    #[test]
+    // psvm -v ""
+    // An empty version is rejected instead of being used to fetch the mapping
+    fn test_empty_version_is_rejected() {
+        let result = crate::Command::try_parse_from(["psvm", "-v", ""]);
+        assert_eq!(
+            result.unwrap_err().kind(),
+            clap::error::ErrorKind::InvalidValue
+        );
+    }
+
+    #[test]
+    // psvm outdated --path runtime / psvm -p runtime outdated
+    // The path is accepted on either side of the subcommand
+    fn test_outdated_accepts_the_path_before_and_after_the_subcommand() {
+        for args in [
+            ["psvm", "outdated", "--path", "runtime"],
+            ["psvm", "-p", "runtime", "outdated"],
+        ] {
+            let cmd = crate::Command::try_parse_from(args).unwrap();
+            assert!(matches!(
+                cmd.subcommand,
+                Some(crate::Subcommand::Outdated { .. })
+            ));
+            assert_eq!(cmd.path, PathBuf::from("runtime"));
+        }
+    }
+
+    #[tokio::test]
+    // The parity-crate-owner crates are injected, so only the Plan.toml file is fetched
+    async fn test_get_version_mapping_with_owners_reuses_the_owners() {
+        let response = r#"
+[[crate]]
+name = "package_published"
+from = "0.1.0"
+to = "0.2.0"
+
+[[crate]]
+name = "package_parity_owned"
+from = "1.0.0"
+to = "1.0.0"
+publish = false
+"#;
+        let version = "with-owners";
+
+        let m = mockito::mock(
+            "GET",
+            format!(
+                "/paritytech/polkadot-sdk/release-crates-io-v{}/Plan.toml",
+                version
+            )
+            .as_str(),
+        )
+        .with_status(200)
+        .with_body(response)
+        .expect(1)
+        .create();
+
+        let parity_owned_crates = HashSet::from(["package_parity_owned".to_string()]);
+        let git_server = &mockito::server_url();
+        let mapping = get_version_mapping_with_owners(
+            &test_client(),
+            git_server,
+            version,
+            &parity_owned_crates,
+        )
+        .await
+        .unwrap();
+
+        m.assert();
+        assert_eq!(
+            mapping,
+            Some(BTreeMap::from([
+                ("package_parity_owned".to_string(), "1.0.0".to_string()),
+                ("package_published".to_string(), "0.2.0".to_string()),
+            ]))
+        );
+    }
+
+    #[tokio::test]
+    // A version without any mapping file is reported as missing, while any other failure
+    // (here the 501 of an unmocked path) is an error
+    async fn test_get_version_mapping_with_owners_only_skips_missing_files() {
+        let version = "not-found";
+        let _m = mockito::mock(
+            "GET",
+            mockito::Matcher::Regex(format!(
+                r"^/paritytech/polkadot-sdk/release-crates-io-v{}/(Plan\.toml|Cargo\.lock)$",
+                version
+            )),
+        )
+        .with_status(404)
+        .expect(2)
+        .create();
+
+        let git_server = &mockito::server_url();
+        let parity_owned_crates = HashSet::new();
+        let mapping = get_version_mapping_with_owners(
+            &test_client(),
+            git_server,
+            version,
+            &parity_owned_crates,
+        )
+        .await
+        .unwrap();
+        assert_eq!(mapping, None);
+        _m.assert();
+
+        let result = get_version_mapping_with_owners(
+            &test_client(),
+            git_server,
+            "server-error",
+            &parity_owned_crates,
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
     #[tokio::test]
     // This test will fetch all available versions, update a generic parachain Cargo.toml file
     // and assert that the Cargo.toml file has been updated (modified)
@@ -291,13 +982,19 @@ source = "registry+https://github.com/rust-lang/crates.io-index"
     // To run this test, ensure you have installed the GitHub CLI and are authenticated
     // cause it will fetch the latest release branches from the GitHub API
     async fn works_for_all_versions() {
-        let release_versions = crate::versions::get_polkadot_sdk_versions().await.unwrap();
+        let release_versions =
+            psvm::versions::get_polkadot_sdk_versions(&test_client(), DEFAULT_MAX_PAGES, false)
+                .await
+                .unwrap();
 
         for version in release_versions {
-            let crates_versions =
-                get_version_mapping_with_fallback(crate::DEFAULT_GIT_SERVER, &version)
-                    .await
-                    .unwrap();
+            let crates_versions = get_version_mapping_with_fallback(
+                &test_client(),
+                crate::DEFAULT_GIT_SERVER,
+                &version,
+            )
+            .await
+            .unwrap();
 
             assert!(
                 crates_versions.len() > 0,
@@ -325,20 +1022,31 @@ source = "registry+https://github.com/rust-lang/crates.io-index"
     // To run this test, ensure you have installed the GitHub CLI and are authenticated
     // cause it will fetch the latest release branches from the GitHub API
     async fn works_for_all_orml_versions() {
-        let release_versions = crate::versions::get_release_branches_versions(Repository::Orml)
+        let release_versions = psvm::versions::get_release_branches_versions(
+            &test_client(),
+            Repository::Orml,
+            DEFAULT_MAX_PAGES,
+        )
+        .await
+        .unwrap();
+
+        for version in release_versions {
+            let mut crates_versions = get_version_mapping_with_fallback(
+                &test_client(),
+                crate::DEFAULT_GIT_SERVER,
+                &version,
+            )
             .await
             .unwrap();
 
-        for version in release_versions {
-            let mut crates_versions =
-                get_version_mapping_with_fallback(crate::DEFAULT_GIT_SERVER, &version)
-                    .await
-                    .unwrap();
-
-            let orml_crates_version =
-                get_orml_crates_and_version(crate::DEFAULT_GIT_SERVER, &version)
-                    .await
-                    .unwrap();
+            let orml_crates_version = get_orml_crates_and_version(
+                &test_client(),
+                crate::DEFAULT_GIT_SERVER,
+                &version,
+                DEFAULT_MAX_PAGES,
+            )
+            .await
+            .unwrap();
             include_orml_crates_in_version_mapping(&mut crates_versions, orml_crates_version);
 
             assert!(
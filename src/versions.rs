@@ -14,7 +14,7 @@
 // limitations under the License.
 
 use regex::Regex;
-use serde::Deserialize;
+use serde::{de::DeserializeOwned, Deserialize};
 use std::collections::{BTreeMap, HashSet};
 
 /// Represents the structure of a Cargo.lock file, including all packages.
@@ -89,21 +89,45 @@ pub struct Workspace {
 
 /// Represents a tag by its name.
 #[derive(Deserialize, Debug)]
-pub struct TagInfo {
+pub(crate) struct TagInfo {
     /// The name of the tag.
     pub name: String,
 }
 
+/// The default `User-Agent` header sent with every request.
+pub const DEFAULT_USER_AGENT: &str = "reqwest";
+
+/// Builds the HTTP client used for all the fetches, sending `user_agent` as the
+/// `User-Agent` header with every request.
+pub fn build_client(user_agent: &str) -> Result<reqwest::Client, Box<dyn std::error::Error>> {
+    Ok(reqwest::Client::builder().user_agent(user_agent).build()?)
+}
+
+/// Builds a GET request with the headers shared by all fetches.
+fn get_request(client: &reqwest::Client, url: &str) -> reqwest::RequestBuilder {
+    client
+        .get(url)
+        .header("Accept", "application/vnd.github.v3+json")
+}
+
+/// The number of items requested per page from the GitHub API.
+const PAGE_SIZE: usize = 100;
+/// The default maximum number of pages fetched from a paginated GitHub API endpoint.
+pub const DEFAULT_MAX_PAGES: u32 = 100;
+
 const POLKADOT_SDK_TAGS_URL: &str =
     "https://api.github.com/repos/paritytech/polkadot-sdk/tags?per_page=100&page=";
 const POLKADOT_SDK_TAGS_GH_CMD_URL: &str = "/repos/paritytech/polkadot-sdk/tags?per_page=100&page=";
 const POLKADOT_SDK_STABLE_TAGS_REGEX: &str = r"^polkadot-stable\d+(-\d+)?$";
+const POLKADOT_SDK_PRERELEASE_TAGS_REGEX: &str = r"^polkadot-stable\d+(-\d+)?(-rc\d+)?$";
+const VALID_VERSION_REGEX: &str = r"^(\d+\.\d+\.\d+|polkadot-stable\d+)(-[0-9A-Za-z]+)*$";
 
 /// Fetches a combined list of Polkadot SDK release versions and stable tag releases.
 ///
 /// This function first retrieves release branch versions from the Polkadot SDK and
 /// then fetches stable tag releases versions. It combines these two lists into a
-/// single list of version strings.
+/// single list of version strings. At most `max_pages` pages are fetched for each list.
+/// Pre-release stable tags are only included if `include_prereleases` is set.
 ///
 /// # Returns
 /// A `Result` containing either a `Vec<String>` of combined version names on success,
@@ -112,11 +136,39 @@ const POLKADOT_SDK_STABLE_TAGS_REGEX: &str = r"^polkadot-stable\d+(-\d+)?$";
 /// # Errors
 /// This function can return an error if either the fetching of release branches versions
 /// or the fetching of stable tag versions encounters an issue.
-pub async fn get_polkadot_sdk_versions() -> Result<Vec<String>, Box<dyn std::error::Error>> {
-    let mut crates_io_releases = get_release_branches_versions(Repository::Psdk).await?;
-    let mut stable_tag_versions = get_stable_tag_versions().await?;
+pub async fn get_polkadot_sdk_versions(
+    client: &reqwest::Client,
+    max_pages: u32,
+    include_prereleases: bool,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut crates_io_releases =
+        get_release_branches_versions(client, Repository::Psdk, max_pages).await?;
+    let mut stable_tag_versions =
+        get_stable_tag_versions(client, max_pages, include_prereleases).await?;
     crates_io_releases.append(&mut stable_tag_versions);
-    Ok(crates_io_releases)
+    Ok(normalize_versions(crates_io_releases))
+}
+
+/// Removes duplicated and invalid entries from a list of versions.
+///
+/// The first occurrence of each version is kept, so the original order is preserved.
+/// Empty entries and entries that are neither a release version (e.g. `1.14.0`) nor a
+/// stable tag (e.g. `polkadot-stable2407-1`) are dropped.
+pub(crate) fn normalize_versions(versions: Vec<String>) -> Vec<String> {
+    let version_regex = Regex::new(VALID_VERSION_REGEX).unwrap();
+    let mut seen = HashSet::new();
+
+    versions
+        .into_iter()
+        .filter(|version| {
+            let valid = version_regex.is_match(version);
+            if !valid {
+                log::debug!("Skipping invalid version {:?}", version);
+            }
+            valid
+        })
+        .filter(|version| seen.insert(version.clone()))
+        .collect()
 }
 
 /// Fetches a list of stable tag versions for the Polkadot SDK from GitHub.
@@ -124,6 +176,8 @@ pub async fn get_polkadot_sdk_versions() -> Result<Vec<String>, Box<dyn std::err
 /// This function queries GitHub's API to retrieve tags for the Polkadot SDK,
 /// filtering them based on a predefined regex to identify stable versions.
 /// If the direct API request fails, it falls back to using the GitHub CLI.
+/// At most `max_pages` pages of tags are fetched, and pre-release tags (e.g.
+/// `polkadot-stable2407-rc1`) are only kept if `include_prereleases` is set.
 ///
 /// # Returns
 /// A `Result` containing either a `Vec<String>` of stable tag names on success,
@@ -133,14 +187,60 @@ pub async fn get_polkadot_sdk_versions() -> Result<Vec<String>, Box<dyn std::err
 /// This function can return an error if the HTTP request fails, if parsing the
 /// response into text fails, if executing the GitHub CLI command fails, or if
 /// parsing the JSON response into `Vec<TagInfo>` fails.
-pub async fn get_stable_tag_versions() -> Result<Vec<String>, Box<dyn std::error::Error>> {
-    let mut release_tags = vec![];
-
-    for page in 1..100 {
-        let response = reqwest::Client::new()
-            .get(format!("{}{}", POLKADOT_SDK_TAGS_URL, page))
-            .header("User-Agent", "reqwest")
-            .header("Accept", "application/vnd.github.v3+json")
+pub async fn get_stable_tag_versions(
+    client: &reqwest::Client,
+    max_pages: u32,
+    include_prereleases: bool,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let tags: Vec<TagInfo> = fetch_pages(
+        client,
+        POLKADOT_SDK_TAGS_URL,
+        POLKADOT_SDK_TAGS_GH_CMD_URL,
+        max_pages,
+    )
+    .await?;
+
+    Ok(filter_stable_tags(tags, include_prereleases))
+}
+
+/// Keeps the names of the stable tags, including the pre-release ones if `include_prereleases`
+/// is set.
+pub(crate) fn filter_stable_tags(tags: Vec<TagInfo>, include_prereleases: bool) -> Vec<String> {
+    let tag_regex = if include_prereleases {
+        Regex::new(POLKADOT_SDK_PRERELEASE_TAGS_REGEX).unwrap()
+    } else {
+        Regex::new(POLKADOT_SDK_STABLE_TAGS_REGEX).unwrap()
+    };
+
+    tags.into_iter()
+        .filter(|tag| tag_regex.is_match(&tag.name))
+        .map(|tag| tag.name)
+        .collect()
+}
+
+/// Fetches all the items of a paginated GitHub API endpoint.
+///
+/// Pages are requested one by one (appending the page number to `url`) until a page with
+/// less than [`PAGE_SIZE`] items is returned, or `max_pages` pages have been fetched. If a
+/// request fails, it falls back to querying the same page through the GitHub CLI (using
+/// `gh_cmd_url`).
+///
+/// A warning is logged if the `max_pages` limit is reached without seeing a short page, as
+/// the results are likely truncated.
+///
+/// # Errors
+/// This function can return an error if the HTTP request fails, if executing the GitHub CLI
+/// command fails, or if parsing the JSON response into `Vec<T>` fails.
+pub(crate) async fn fetch_pages<T: DeserializeOwned>(
+    client: &reqwest::Client,
+    url: &str,
+    gh_cmd_url: &str,
+    max_pages: u32,
+) -> Result<Vec<T>, Box<dyn std::error::Error>> {
+    let mut items = vec![];
+
+    for page in 1..=max_pages {
+        let response = get_request(client, &format!("{}{}", url, page))
             .send()
             .await?;
 
@@ -156,32 +256,31 @@ pub async fn get_stable_tag_versions() -> Result<Vec<String>, Box<dyn std::error
                         "Accept: application/vnd.github+json",
                         "-H",
                         "X-GitHub-Api-Version: 2022-11-28",
-                        &format!("{}{}", POLKADOT_SDK_TAGS_GH_CMD_URL, page),
+                        &format!("{}{}", gh_cmd_url, page),
                     ])
                     .output()?
                     .stdout,
             )?
         };
 
-        let tag_branches: Vec<TagInfo> = serde_json::from_str(&output)?;
-        let tag_regex = Regex::new(POLKADOT_SDK_STABLE_TAGS_REGEX).unwrap();
-
-        let stable_tag_branches = tag_branches
-            .iter()
-            .filter(|b| tag_regex.is_match(&b.name))
-            .map(|branch| branch.name.to_string());
-
-        release_tags = release_tags
-            .into_iter()
-            .chain(stable_tag_branches)
-            .collect();
+        let page_items: Vec<T> = serde_json::from_str(&output)?;
+        let page_len = page_items.len();
+        items.extend(page_items);
 
-        if tag_branches.len() < 100 {
-            break;
+        if page_len < PAGE_SIZE {
+            return Ok(items);
         }
     }
 
-    Ok(release_tags)
+    log::warn!(
+        url = url,
+        max_pages = max_pages;
+        "Reached the limit of {} pages fetching {}, results may be truncated (see '--max-pages')",
+        max_pages,
+        url
+    );
+
+    Ok(items)
 }
 
 /// Fetches the ORML crates and their versions for a specific version of Polkadot.
@@ -193,8 +292,10 @@ pub async fn get_stable_tag_versions() -> Result<Vec<String>, Box<dyn std::error
 ///
 /// # Arguments
 ///
+/// * `client` - The HTTP client used for the requests (see [`build_client`]).
 /// * `base_url` - The base URL of GitHub.
 /// * `version` - The release version of the Polkadot-sdk for which ORML crates' versions are being fetched.
+/// * `max_pages` - The maximum number of ORML branch pages to fetch.
 ///
 /// # Returns
 ///
@@ -210,12 +311,17 @@ pub async fn get_stable_tag_versions() -> Result<Vec<String>, Box<dyn std::error
 ///
 /// # Examples
 ///
-/// ```
+/// ```no_run
+/// use psvm::versions::{
+///     build_client, get_orml_crates_and_version, DEFAULT_MAX_PAGES, DEFAULT_USER_AGENT,
+/// };
+///
 /// #[tokio::main]
 /// async fn main() {
+///     let client = build_client(DEFAULT_USER_AGENT).unwrap();
 ///     let base_url = "https://raw.githubusercontent.com";
 ///     let version = "1.12.0";
-///     match get_orml_crates_and_version(base_url, version).await {
+///     match get_orml_crates_and_version(&client, base_url, version, DEFAULT_MAX_PAGES).await {
 ///         Ok(Some(orml_toml)) => println!("ORML crates: {:?}", orml_toml),
 ///         Ok(None) => println!("No matching ORML version found."),
 ///         Err(e) => println!("Error fetching ORML crates: {}", e),
@@ -223,10 +329,12 @@ pub async fn get_stable_tag_versions() -> Result<Vec<String>, Box<dyn std::error
 /// }
 /// ```
 pub async fn get_orml_crates_and_version(
+    client: &reqwest::Client,
     base_url: &str,
     version: &str,
+    max_pages: u32,
 ) -> Result<Option<OrmlToml>, Box<dyn std::error::Error>> {
-    if get_release_branches_versions(Repository::Orml)
+    if get_release_branches_versions(client, Repository::Orml, max_pages)
         .await?
         .contains(&version.to_string())
     {
@@ -234,12 +342,7 @@ pub async fn get_orml_crates_and_version(
             "{}/open-web3-stack/open-runtime-module-library/polkadot-v{}/Cargo.dev.toml",
             base_url, version
         );
-        let response = reqwest::Client::new()
-            .get(&version_url)
-            .header("User-Agent", "reqwest")
-            .header("Accept", "application/vnd.github.v3+json")
-            .send()
-            .await?;
+        let response = get_request(client, &version_url).send().await?;
 
         let content = response.text().await?;
 
@@ -271,8 +374,23 @@ pub async fn get_orml_crates_and_version(
 /// # Examples
 ///
 /// ```
+/// use psvm::versions::{include_orml_crates_in_version_mapping, OrmlToml};
+/// use std::collections::BTreeMap;
+///
+/// let orml_toml: OrmlToml = toml::from_str(
+///     r#"
+/// [workspace]
+/// members = ["tokens", "xtokens"]
+///
+/// [workspace.metadata.orml]
+/// crates-version = "0.7.0"
+/// "#,
+/// )
+/// .unwrap();
+///
 /// let mut version_map: BTreeMap<String, String> = BTreeMap::new();
 /// include_orml_crates_in_version_mapping(&mut version_map, Some(orml_toml));
+/// assert_eq!(version_map.get("orml-tokens"), Some(&"0.7.0".to_string()));
 /// ```
 pub fn include_orml_crates_in_version_mapping(
     crates_versions: &mut BTreeMap<String, String>,
@@ -289,13 +407,14 @@ pub fn include_orml_crates_in_version_mapping(
 }
 
 pub async fn get_version_mapping_with_fallback(
+    client: &reqwest::Client,
     base_url: &str,
     version: &str,
 ) -> Result<BTreeMap<String, String>, Box<dyn std::error::Error>> {
-    let result = get_version_mapping(base_url, version, "Plan.toml").await;
+    let result = get_version_mapping(client, base_url, version, "Plan.toml").await;
 
     match result {
-        Err(_) => get_version_mapping(base_url, version, "Cargo.lock").await,
+        Err(_) => get_version_mapping(client, base_url, version, "Cargo.lock").await,
         Ok(_) => result,
     }
 }
@@ -316,32 +435,119 @@ fn version_to_url(base_url: &str, version: &str, source: &str) -> String {
     )
 }
 
-pub async fn get_version_mapping(
+/// Like [`get_version_mapping_with_fallback`], but reusing the crates owned by
+/// parity-crate-owner (see [`get_parity_crate_owner_crates`]) instead of fetching them again,
+/// e.g. when fetching the mappings of many versions in a row.
+///
+/// Returns `Ok(None)` if the version has neither a `Plan.toml` nor a `Cargo.lock` file.
+///
+/// # Errors
+///
+/// Unlike [`get_version_mapping_with_fallback`], only a missing `Plan.toml` file falls back to
+/// the `Cargo.lock` one: any other failure (e.g. a rate-limited request) is returned.
+pub async fn get_version_mapping_with_owners(
+    client: &reqwest::Client,
+    base_url: &str,
+    version: &str,
+    parity_owned_crates: &HashSet<String>,
+) -> Result<Option<BTreeMap<String, String>>, Box<dyn std::error::Error>> {
+    if let Some(content) =
+        get_existing_version_source(client, base_url, version, "Plan.toml").await?
+    {
+        return get_plan_packages(&content, parity_owned_crates).map(Some);
+    }
+
+    match get_existing_version_source(client, base_url, version, "Cargo.lock").await? {
+        Some(content) => get_cargo_packages(&content).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Like [`get_version_source`], but returning `Ok(None)` if the file doesn't exist.
+async fn get_existing_version_source(
+    client: &reqwest::Client,
     base_url: &str,
     version: &str,
     source: &str,
-) -> Result<BTreeMap<String, String>, Box<dyn std::error::Error>> {
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    match get_version_source(client, base_url, version, source).await {
+        Ok(content) => Ok(Some(content)),
+        Err(err)
+            if err
+                .downcast_ref::<reqwest::Error>()
+                .and_then(|err| err.status())
+                == Some(reqwest::StatusCode::NOT_FOUND) =>
+        {
+            Ok(None)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Fetches the content of the `source` file (`Plan.toml` or `Cargo.lock`) of a version.
+async fn get_version_source(
+    client: &reqwest::Client,
+    base_url: &str,
+    version: &str,
+    source: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
     let url = version_to_url(base_url, version, source);
-    let response = reqwest::Client::new()
-        .get(&url)
-        .header("User-Agent", "reqwest")
-        .header("Accept", "application/vnd.github.v3+json")
-        .send()
-        .await?;
+    let response = get_request(client, &url).send().await?;
 
-    let content = match response.error_for_status() {
-        Ok(response) => response.text().await?,
-        Err(err) => return Err(err.into()),
-    };
+    match response.error_for_status() {
+        Ok(response) => Ok(response.text().await?),
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub async fn get_version_mapping(
+    client: &reqwest::Client,
+    base_url: &str,
+    version: &str,
+    source: &str,
+) -> Result<BTreeMap<String, String>, Box<dyn std::error::Error>> {
+    let content = get_version_source(client, base_url, version, source).await?;
 
     match source {
         "Cargo.lock" => get_cargo_packages(&content),
-        "Plan.toml" => get_plan_packages(&content).await,
+        "Plan.toml" => {
+            let parity_owned_crates = get_parity_crate_owner_crates(client).await?;
+            get_plan_packages(&content, &parity_owned_crates)
+        }
         _ => panic!("Unknown source: {}", source),
     }
 }
 
-fn get_cargo_packages(
+/// Parses the content of a Polkadot SDK `Cargo.lock` file into a crate -> version mapping.
+///
+/// Only the local packages (those without a `source`) are kept, as the remote ones are
+/// dependencies of the Polkadot SDK rather than crates released by it.
+///
+/// # Errors
+///
+/// This function returns an error if the content is not a valid `Cargo.lock` file.
+///
+/// # Examples
+///
+/// ```
+/// use psvm::versions::get_cargo_packages;
+///
+/// let content = r#"
+/// [[package]]
+/// name = "sp-core"
+/// version = "28.0.0"
+///
+/// [[package]]
+/// name = "serde"
+/// version = "1.0.197"
+/// source = "registry+https://github.com/rust-lang/crates.io-index"
+/// "#;
+///
+/// let mapping = get_cargo_packages(content).unwrap();
+/// assert_eq!(mapping.len(), 1);
+/// assert_eq!(mapping.get("sp-core"), Some(&"28.0.0".to_string()));
+/// ```
+pub fn get_cargo_packages(
     content: &str,
 ) -> Result<BTreeMap<String, String>, Box<dyn std::error::Error>> {
     let cargo_lock: CargoLock = toml::from_str(content)?;
@@ -357,13 +563,54 @@ fn get_cargo_packages(
     Ok(cargo_packages)
 }
 
-async fn get_plan_packages(
+/// Parses the content of a Polkadot SDK `Plan.toml` file into a crate -> version mapping.
+///
+/// The crates published in the release are kept, along with the crates not published in
+/// the release but owned by `parity-crate-owner` on crates.io (as given by
+/// `parity_owned_crates`, see [`get_parity_crate_owner_crates`]), unless they are
+/// placeholders (i.e. both `from` and `to` are `0.0.0`).
+///
+/// # Errors
+///
+/// This function returns an error if the content is not a valid `Plan.toml` file.
+///
+/// # Examples
+///
+/// ```
+/// use psvm::versions::get_plan_packages;
+/// use std::collections::HashSet;
+///
+/// let content = r#"
+/// [[crate]]
+/// name = "sp-core"
+/// from = "27.0.0"
+/// to = "28.0.0"
+///
+/// [[crate]]
+/// name = "sp-io"
+/// from = "30.0.0"
+/// to = "30.0.0"
+/// publish = false
+///
+/// [[crate]]
+/// name = "node-template"
+/// from = "0.0.0"
+/// to = "0.0.0"
+/// publish = false
+/// "#;
+///
+/// let parity_owned_crates = HashSet::from(["sp-io".to_string(), "node-template".to_string()]);
+/// let mapping = get_plan_packages(content, &parity_owned_crates).unwrap();
+/// assert_eq!(mapping.len(), 2);
+/// assert_eq!(mapping.get("sp-core"), Some(&"28.0.0".to_string()));
+/// assert_eq!(mapping.get("sp-io"), Some(&"30.0.0".to_string()));
+/// ```
+pub fn get_plan_packages(
     content: &str,
+    parity_owned_crates: &HashSet<String>,
 ) -> Result<BTreeMap<String, String>, Box<dyn std::error::Error>> {
     let plan_toml: PlanToml = toml::from_str(content)?;
 
-    let parity_owned_crates = get_parity_crate_owner_crates().await?;
-
     // Filter local packages and collect them into a JSON object
     let plan_packages: BTreeMap<_, _> = plan_toml
         .crates
@@ -445,7 +692,9 @@ fn get_repository_info(repository: &Repository) -> RepositoryInfo {
 ///
 /// # Arguments
 ///
+/// * `client` - The HTTP client used for the requests (see [`build_client`]).
 /// * `repository` - A `Repository` enum specifying whether to query the ORML or Polkadot SDK repository.
+/// * `max_pages` - The maximum number of branch pages to fetch.
 ///
 /// # Returns
 ///
@@ -462,93 +711,73 @@ fn get_repository_info(repository: &Repository) -> RepositoryInfo {
 /// # Examples
 ///
 /// ```no_run
+/// use psvm::versions::{
+///     build_client, get_release_branches_versions, Repository, DEFAULT_MAX_PAGES,
+///     DEFAULT_USER_AGENT,
+/// };
+///
 /// #[tokio::main]
 /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let client = build_client(DEFAULT_USER_AGENT)?;
+///
 ///     let orml_repository = Repository::Orml;
-///     let orml_versions = get_release_branches_versions(orml_repository).await?;
+///     let orml_versions =
+///         get_release_branches_versions(&client, orml_repository, DEFAULT_MAX_PAGES).await?;
 ///     println!("Orml Release versions: {:?}", orml_versions);
 ///
 ///     let psdk_repository = Repository::Psdk;
-///     let psdk_versions = get_release_branches_versions(psdk_repository).await?;
+///     let psdk_versions =
+///         get_release_branches_versions(&client, psdk_repository, DEFAULT_MAX_PAGES).await?;
 ///     println!("Polkadot-sdk Release versions: {:?}", psdk_versions);
 ///
 ///     Ok(())
 /// }
 /// ```
 pub async fn get_release_branches_versions(
+    client: &reqwest::Client,
     repository: Repository,
+    max_pages: u32,
 ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-    let mut release_branches = vec![];
     let repository_info = get_repository_info(&repository);
 
-    for page in 1..100 {
-        // currently there's 5 pages, so 100 should be enough
-        let response = reqwest::Client::new()
-            .get(format!("{}{}", repository_info.branches_url, page))
-            .header("User-Agent", "reqwest")
-            .header("Accept", "application/vnd.github.v3+json")
-            .send()
-            .await?;
-
-        let output = if response.status().is_success() {
-            response.text().await?
-        } else {
-            // query the github api using gh command
-            String::from_utf8(
-                std::process::Command::new("gh")
-                    .args([
-                        "api",
-                        "-H",
-                        "Accept: application/vnd.github+json",
-                        "-H",
-                        "X-GitHub-Api-Version: 2022-11-28",
-                        &format!("{}{}", repository_info.gh_cmd_url, page),
-                    ])
-                    .output()?
-                    .stdout,
-            )?
-        };
-
-        let branches: Vec<Branch> = serde_json::from_str(&output)?;
-
-        let version_branches = branches
-            .iter()
-            .filter(|b| b.name.starts_with(&repository_info.version_filter_string))
-            .filter(|b| (b.name != "polkadot-v1.0.0")) // This is in place to filter that particular orml version as it is not a valid polkadot-sdk release version
-            .map(|branch| {
-                branch
-                    .name
-                    .replace(&repository_info.version_replace_string, "")
-            });
-
-        release_branches = release_branches
-            .into_iter()
-            .chain(version_branches)
-            .collect();
-
-        if branches.len() < 100 {
-            break;
-        }
-    }
+    let branches: Vec<Branch> = fetch_pages(
+        client,
+        &repository_info.branches_url,
+        &repository_info.gh_cmd_url,
+        max_pages,
+    )
+    .await?;
+
+    let release_branches = branches
+        .iter()
+        .filter(|b| b.name.starts_with(&repository_info.version_filter_string))
+        .filter(|b| b.name != "polkadot-v1.0.0") // This is in place to filter that particular orml version as it is not a valid polkadot-sdk release version
+        .map(|branch| {
+            branch
+                .name
+                .replace(&repository_info.version_replace_string, "")
+        })
+        .collect();
 
-    Ok(release_branches)
+    Ok(normalize_versions(release_branches))
 }
 
-pub async fn get_parity_crate_owner_crates() -> Result<HashSet<String>, Box<dyn std::error::Error>>
-{
+pub async fn get_parity_crate_owner_crates(
+    client: &reqwest::Client,
+) -> Result<HashSet<String>, Box<dyn std::error::Error>> {
     let mut parity_crates = HashSet::new();
 
     for page in 1..=10 {
         // Currently there are 7 pages (so this at most 1s)
-        let response = reqwest::Client::new()
-            .get(format!(
+        let response = get_request(
+            client,
+            &format!(
                 "https://crates.io/api/v1/crates?page={}&per_page=100&user_id=150167", // parity-crate-owner
                 page
-            ))
-            .header("User-Agent", "reqwest")
-            .header("Accept", "application/vnd.github.v3+json")
-            .send()
-            .await?;
+            ),
+        )
+        .send()
+        .await?;
 
         let output = response.text().await?;
 
@@ -571,3 +800,140 @@ pub async fn get_parity_crate_owner_crates() -> Result<HashSet<String>, Box<dyn
 
     Ok(parity_crates)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Mutex, Once};
+
+    /// Records the messages of every log record emitted by the tests, so they can be asserted.
+    struct CapturingLogger;
+
+    static CAPTURED_LOGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    impl log::Log for CapturingLogger {
+        fn enabled(&self, _metadata: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            CAPTURED_LOGS
+                .lock()
+                .unwrap()
+                .push(format!("{} {}", record.level(), record.args()));
+        }
+
+        fn flush(&self) {}
+    }
+
+    fn init_capturing_logger() {
+        static INIT: Once = Once::new();
+        INIT.call_once(|| {
+            log::set_logger(&CapturingLogger).unwrap();
+            log::set_max_level(log::LevelFilter::Trace);
+        });
+    }
+
+    fn captured_logs_contain(message: &str) -> bool {
+        CAPTURED_LOGS
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|log| log == message)
+    }
+
+    #[test]
+    fn test_normalize_versions_removes_duplicates_and_invalid_entries() {
+        let versions = [
+            "1.14.0",
+            "",
+            "polkadot-stable2407",
+            "1.14.0",
+            "not-a-version",
+            "polkadot-stable2407-1",
+            " ",
+            "polkadot-stable2407",
+            "1.3.0",
+        ]
+        .map(String::from)
+        .to_vec();
+
+        assert_eq!(
+            normalize_versions(versions),
+            vec![
+                "1.14.0",
+                "polkadot-stable2407",
+                "polkadot-stable2407-1",
+                "1.3.0"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    // Every page is full, so the pagination stops at the limit and warns about it
+    async fn test_fetch_pages_warns_when_max_pages_is_reached() {
+        init_capturing_logger();
+
+        let tags = (0..100)
+            .map(|i| format!(r#"{{"name": "polkadot-stable{}"}}"#, i))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let _m = mockito::mock("GET", mockito::Matcher::Regex(r"^/max-pages/tags".into()))
+            .with_status(200)
+            .with_body(format!("[{}]", tags))
+            .expect(3)
+            .create();
+
+        let url = format!(
+            "{}/max-pages/tags?per_page=100&page=",
+            mockito::server_url()
+        );
+        let tags: Vec<TagInfo> =
+            fetch_pages(&build_client(DEFAULT_USER_AGENT).unwrap(), &url, "", 3)
+                .await
+                .unwrap();
+
+        _m.assert();
+        assert_eq!(tags.len(), 300);
+        assert!(captured_logs_contain(&format!(
+            "WARN Reached the limit of 3 pages fetching {}, results may be truncated (see '--max-pages')",
+            url
+        )));
+    }
+
+    #[test]
+    // cargo psvm -l [--include-prereleases]
+    // The rc tags are only listed when the pre-releases are included
+    fn test_filter_stable_tags_with_and_without_prereleases() {
+        let tags = || {
+            Vec::from(
+                [
+                    "polkadot-stable2407",
+                    "polkadot-stable2407-rc1",
+                    "polkadot-stable2407-1",
+                    "polkadot-stable2407-1-rc2",
+                    "polkadot-v1.14.0",
+                    "polkadot-stable2407-beta",
+                ]
+                .map(|name| TagInfo {
+                    name: name.to_string(),
+                }),
+            )
+        };
+
+        assert_eq!(
+            filter_stable_tags(tags(), false),
+            vec!["polkadot-stable2407", "polkadot-stable2407-1"]
+        );
+        assert_eq!(
+            filter_stable_tags(tags(), true),
+            vec![
+                "polkadot-stable2407",
+                "polkadot-stable2407-rc1",
+                "polkadot-stable2407-1",
+                "polkadot-stable2407-1-rc2"
+            ]
+        );
+    }
+}